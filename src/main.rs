@@ -1,10 +1,18 @@
 use clap::{Parser, ValueEnum};
 // Remove glob import
-use ignore::{WalkBuilder, overrides::OverrideBuilder};
+use crossbeam_channel::unbounded;
+use ignore::{
+    Match, WalkBuilder, WalkState,
+    gitignore::{Gitignore, GitignoreBuilder},
+    overrides::{Override, OverrideBuilder},
+    types::TypesBuilder,
+};
 use std::{
+    collections::HashSet,
     fs,
-    io::{self, Write},
+    io,
     path::{Path, PathBuf},
+    sync::{Arc, Mutex},
 };
 // Remove walkdir import if no longer needed elsewhere
 // use walkdir::WalkDir;
@@ -52,6 +60,44 @@ struct Args {
     /// Respect .gitignore files found in the directory structure.
     #[arg(long, default_value_t = true)]
     respect_gitignore: bool,
+
+    /// Only include files of the given type (e.g. `rust`, `py`, `md`). Repeatable.
+    #[arg(short = 't', long = "type", value_name = "TYPE", num_args = 0..)]
+    file_type: Vec<String>,
+
+    /// Exclude files of the given type (e.g. `rust`, `py`, `md`). Repeatable.
+    #[arg(short = 'T', long = "type-not", value_name = "TYPE", num_args = 0..)]
+    type_not: Vec<String>,
+
+    /// Define a custom file type, e.g. `--type-add 'web:*.{html,css}'`.
+    #[arg(long = "type-add", value_name = "NAME:GLOB", num_args = 0..)]
+    type_add: Vec<String>,
+
+    /// Number of worker threads to use for walking/reading (0 lets the `ignore` crate decide).
+    #[arg(short = 'j', long, value_name = "THREADS", default_value_t = 0)]
+    threads: usize,
+
+    /// Reduce filtering (repeatable): -u disables .gitignore/.ignore rules,
+    /// -uu also shows hidden files/dirs, -uuu also includes binary files (lossily).
+    #[arg(short = 'u', long, action = clap::ArgAction::Count)]
+    unrestricted: u8,
+
+    /// Don't honor the global gitignore (e.g. `core.excludesFile`).
+    #[arg(long)]
+    no_global_ignore: bool,
+
+    /// Don't honor `.git/info/exclude`.
+    #[arg(long)]
+    no_git_exclude: bool,
+
+    /// Don't fold .gitignore files from ancestor directories (up to the repo root) into the walk.
+    #[arg(long)]
+    no_parent_ignore: bool,
+
+    /// For each excluded entry, record which ignore rule and source file caused the
+    /// exclusion and print it in a separate "Ignored Entries" section.
+    #[arg(long)]
+    explain: bool,
 }
 
 // --- Data Structures (Unchanged) ---
@@ -68,6 +114,241 @@ struct DirEntryInfo {
 }
 // ---------------------
 
+// A single walked entry as produced by a parallel worker, before the
+// post-walk sort restores deterministic (depth-first, lexical) ordering.
+struct WalkItem {
+    path: PathBuf,
+    name: String,
+    is_dir: bool,
+    depth: usize,
+    content: Option<String>,
+}
+
+/// Why a path was left out of the walk, for `--explain` mode.
+struct IgnoredEntry {
+    path: PathBuf,
+    pattern: String,
+    source: String,
+}
+
+/// Walk upward from `root`'s ancestors collecting every `filename` found,
+/// stopping once a directory containing `.git` has been checked (mirroring
+/// how watchexec's ignore loader ascends to the repo root).
+///
+/// Like `ignore::WalkBuilder::parents(true)`, this only takes effect when a
+/// `.git` is actually found somewhere above `root`: if the ascent reaches the
+/// filesystem root without finding one, there's no repo root to anchor on, so
+/// none of the collected files apply and an empty list is returned.
+fn find_ancestor_ignore_files(root: &Path, filename: &str) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut current = root.parent();
+    while let Some(dir) = current {
+        let candidate = dir.join(filename);
+        if candidate.is_file() {
+            found.push(candidate);
+        }
+        if dir.join(".git").exists() {
+            return found;
+        }
+        current = dir.parent();
+    }
+    Vec::new()
+}
+
+/// Same as [`find_ancestor_ignore_files`], specialized to `.gitignore` (used
+/// for the startup diagnostic).
+fn find_ancestor_gitignores(root: &Path) -> Vec<PathBuf> {
+    find_ancestor_ignore_files(root, ".gitignore")
+}
+
+/// Build a `Gitignore` matcher mirroring the ignore sources the real walk
+/// consults (root + ancestor .gitignore/.ignore files, `root_path`'s own
+/// `.git/info/exclude`, and the global gitignore at `$HOME/.config/git/ignore`),
+/// for use in `--explain` mode. A `core.excludesFile` set elsewhere is not read.
+/// At `-u` and above the walk consults no ignore files, so the matcher is empty.
+fn build_explain_gitignore(root_path: &Path, args: &Args) -> Gitignore {
+    if args.unrestricted > 0 {
+        return Gitignore::empty();
+    }
+    let mut builder = GitignoreBuilder::new(root_path);
+
+    builder.add(root_path.join(".gitignore"));
+    builder.add(root_path.join(".ignore"));
+    if !args.no_parent_ignore {
+        for gitignore_path in find_ancestor_ignore_files(root_path, ".gitignore") {
+            builder.add(gitignore_path);
+        }
+        for ignore_path in find_ancestor_ignore_files(root_path, ".ignore") {
+            builder.add(ignore_path);
+        }
+    }
+    if !args.no_git_exclude {
+        let exclude_path = root_path.join(".git").join("info").join("exclude");
+        if exclude_path.is_file() {
+            builder.add(exclude_path);
+        }
+    }
+    if !args.no_global_ignore {
+        if let Some(home) = std::env::var_os("HOME") {
+            let global_path = PathBuf::from(home).join(".config/git/ignore");
+            if global_path.is_file() {
+                builder.add(global_path);
+            }
+        }
+    }
+
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+/// Build one `Override` per `--exclude` pattern so `--explain` can attribute a
+/// match back to the exact pattern string that caused it. `overrides::Glob`
+/// exposes no public accessors to recover that from a combined `Override`, so
+/// each pattern gets its own single-pattern matcher instead.
+fn build_per_pattern_excludes(root_path: &Path, patterns: &[String]) -> Vec<(String, Override)> {
+    patterns
+        .iter()
+        .filter_map(|pattern| {
+            let mut builder = OverrideBuilder::new(root_path);
+            builder.add(&format!("!{}", pattern)).ok()?;
+            let matcher = builder.build().ok()?;
+            Some((pattern.clone(), matcher))
+        })
+        .collect()
+}
+
+/// The first `.`-prefixed path component between `root_path` and `path`, if
+/// any — i.e. what the default hidden-file filter would have excluded on.
+fn first_hidden_component(root_path: &Path, path: &Path) -> Option<String> {
+    path.strip_prefix(root_path).ok()?.components().find_map(|c| {
+        let s = c.as_os_str().to_str()?;
+        s.starts_with('.').then(|| s.to_string())
+    })
+}
+
+/// Work out which matcher is responsible for excluding `path`, in the same
+/// precedence `ignore`'s own walker uses: overrides (`--exclude`) beat
+/// gitignore-style rules, which beat `--type`/`--type-not` filters, which beat
+/// the default hidden-file filter (lowest precedence, since it's the first
+/// thing any other rule can override).
+fn reason_for_exclusion(
+    root_path: &Path,
+    path: &Path,
+    is_dir: bool,
+    types: &ignore::types::Types,
+    selected_types: &[String],
+    exclude_patterns: &[(String, Override)],
+    gitignore: &Gitignore,
+) -> Option<(String, String)> {
+    if let Some((pattern, _)) = exclude_patterns
+        .iter()
+        .find(|(_, matcher)| matches!(matcher.matched(path, is_dir), Match::Ignore(_)))
+    {
+        return Some(("--exclude".to_string(), pattern.clone()));
+    }
+
+    if let Match::Ignore(glob) = gitignore.matched_path_or_any_parents(path, is_dir) {
+        let source = glob
+            .from()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "gitignore".to_string());
+        return Some((source, glob.original().to_string()));
+    }
+
+    if let Match::Ignore(glob) = types.matched(path, is_dir) {
+        // `file_type_def()` is `None` for `UnmatchedIgnore`, i.e. `-t`/`--type`
+        // selection filtering: the file just didn't match any selected type,
+        // rather than being hit by an explicit `-T`/`--type-not` glob.
+        let pattern = match glob.file_type_def() {
+            Some(def) => def.name().to_string(),
+            None if !selected_types.is_empty() => {
+                format!("not any of: {}", selected_types.join(", "))
+            }
+            None => "type filter".to_string(),
+        };
+        return Some(("type filter".to_string(), pattern));
+    }
+
+    if let Some(name) = first_hidden_component(root_path, path) {
+        return Some(("hidden".to_string(), name));
+    }
+
+    None
+}
+
+/// Re-walk `root_path` with every filter disabled, and for each path that
+/// didn't make it into `included_paths`, work out which matcher excluded it
+/// and what rule/source is responsible.
+///
+/// Once a directory itself is found to be excluded, its descendants share the
+/// same reason, so the walk is pruned there via `filter_entry` instead of
+/// separately re-visiting (and re-matching) every file underneath — this
+/// keeps a single excluded `.git`/`node_modules`/build-output directory from
+/// multiplying this single-threaded re-walk's I/O across a large tree.
+fn find_ignored_entries(
+    root_path: &Path,
+    included_paths: &HashSet<PathBuf>,
+    types: &ignore::types::Types,
+    selected_types: &[String],
+    exclude_patterns: &[(String, Override)],
+    gitignore: &Gitignore,
+) -> Vec<IgnoredEntry> {
+    let root_path = root_path.to_path_buf();
+    let included_paths = included_paths.clone();
+    let types = types.clone();
+    let selected_types = selected_types.to_vec();
+    let exclude_patterns = exclude_patterns.to_vec();
+    let gitignore = gitignore.clone();
+
+    let ignored = Arc::new(Mutex::new(Vec::new()));
+    let ignored_in_filter = Arc::clone(&ignored);
+
+    let walker = WalkBuilder::new(&root_path)
+        .standard_filters(false)
+        .hidden(false)
+        .filter_entry(move |entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+            let path = entry.path();
+            if included_paths.contains(path) {
+                return true;
+            }
+            let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+
+            match reason_for_exclusion(
+                &root_path,
+                path,
+                is_dir,
+                &types,
+                &selected_types,
+                &exclude_patterns,
+                &gitignore,
+            ) {
+                Some((source, pattern)) => {
+                    ignored_in_filter.lock().unwrap().push(IgnoredEntry {
+                        path: path.to_path_buf(),
+                        pattern,
+                        source,
+                    });
+                    false
+                }
+                None => true,
+            }
+        })
+        .build();
+
+    for entry_result in walker {
+        let _ = entry_result;
+    }
+
+    let mut ignored = Arc::try_unwrap(ignored)
+        .unwrap_or_else(|_| unreachable!("no outstanding references after the walk completes"))
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    ignored.sort_by(|a, b| a.path.cmp(&b.path));
+    ignored
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
 
@@ -116,6 +397,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
+    // --- Build file-type matcher for --type/--type-not/--type-add ---
+    let mut types_builder = TypesBuilder::new();
+    types_builder.add_defaults();
+    for def in &args.type_add {
+        if let Err(e) = types_builder.add_def(def) {
+            eprintln!("Warning: Invalid --type-add definition '{}': {} (Ignoring)", def, e);
+        }
+    }
+    for name in &args.file_type {
+        types_builder.select(name);
+    }
+    for name in &args.type_not {
+        types_builder.negate(name);
+    }
+    let types = match types_builder.build() {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Error: Failed to build file-type rules: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Keep copies around for --explain, since `types`/`overrides` are consumed by the walk builder.
+    let explain_types = args.explain.then(|| types.clone());
+    let explain_exclude_patterns = args
+        .explain
+        .then(|| build_per_pattern_excludes(&root_path, &args.exclude));
+    let explain_gitignore = args.explain.then(|| build_explain_gitignore(&root_path, &args));
+
     // --- Collect directory structure and file contents using 'ignore' crate ---
     let mut dir_entries: Vec<DirEntryInfo> = Vec::new();
     let mut file_contents: Vec<FileInfo> = Vec::new();
@@ -127,81 +437,167 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if !args.exclude.is_empty() {
         eprintln!("Applying exclude patterns: {:?}", args.exclude);
     }
+    if !args.file_type.is_empty() {
+        eprintln!("Restricting to file types: {:?}", args.file_type);
+    }
+    if !args.type_not.is_empty() {
+        eprintln!("Excluding file types: {:?}", args.type_not);
+    }
+    if args.unrestricted > 0 {
+        eprintln!("Unrestricted level: {}", args.unrestricted);
+    }
+    if !args.no_parent_ignore && args.unrestricted == 0 {
+        let ancestor_gitignores = find_ancestor_gitignores(&root_path);
+        if !ancestor_gitignores.is_empty() {
+            eprintln!(
+                "Folding in ancestor .gitignore files: {:?}",
+                ancestor_gitignores
+            );
+        }
+    }
+
+    // Escalating -u/--unrestricted levels, à la ripgrep/statix:
+    //   0: respect .gitignore/.ignore/git_exclude/git_global as configured
+    //   1 (-u):   ignore all of the above (show everything git would hide)
+    //   2 (-uu):  also walk into hidden files/dirs
+    //   3 (-uuu): also include files that fail UTF-8 detection (read lossily)
+    let respect_ignore_files = args.unrestricted == 0;
+    // `WalkBuilder::hidden(true)` means "skip hidden files", so this is the
+    // inverse of "show hidden files" — true at levels 0-1, false at -uu+.
+    let hide_hidden = args.unrestricted < 2;
+    let read_binary_lossy = args.unrestricted >= 3;
 
     // --- Configure WalkBuilder ---
     let mut walk_builder = WalkBuilder::new(&root_path);
     walk_builder
-        .git_ignore(args.respect_gitignore) // Control .gitignore handling
-        .ignore(false) // Don't use .ignore files
-        .git_global(false) // Don't use global gitignore
-        .git_exclude(false) // Don't use .git/info/exclude
-        .overrides(overrides); // Apply command-line --exclude patterns
+        .git_ignore(args.respect_gitignore && respect_ignore_files) // Control .gitignore handling
+        .ignore(respect_ignore_files) // Honor dedicated .ignore files (fd/watchexec-style)
+        .git_global(!args.no_global_ignore && respect_ignore_files) // Honor the global gitignore
+        .git_exclude(!args.no_git_exclude && respect_ignore_files) // Honor .git/info/exclude
+        .hidden(hide_hidden) // -uu also traverses hidden files/dirs
+        .parents(!args.no_parent_ignore) // Fold ancestor .gitignore files into the walk
+        .overrides(overrides) // Apply command-line --exclude patterns
+        .types(types) // Apply --type/--type-not/--type-add filters
+        .threads(args.threads); // Number of walker/reader threads
 
     if args.max_depth > 0 {
         // Add 1 because WalkBuilder depth includes the root (depth 0)
         walk_builder.max_depth(Some(args.max_depth + 1));
     }
 
-    // --- Iterate ---
-    for entry_result in walk_builder.build() {
-        let entry = match entry_result {
-            Ok(entry) => entry,
-            Err(e) => {
-                eprintln!("Warning: Error accessing entry: {}", e);
-                continue;
+    // --- Walk and read files in parallel, collecting results over a channel ---
+    let (tx, rx) = unbounded::<WalkItem>();
+
+    walk_builder.build_parallel().run(|| {
+        let tx = tx.clone();
+        Box::new(move |entry_result| {
+            let entry = match entry_result {
+                Ok(entry) => entry,
+                Err(e) => {
+                    eprintln!("Warning: Error accessing entry: {}", e);
+                    return WalkState::Continue;
+                }
+            };
+
+            // Skip the root directory itself (depth 0)
+            if entry.depth() == 0 {
+                return WalkState::Continue;
             }
-        };
 
-        // Skip the root directory itself (depth 0)
-        if entry.depth() == 0 {
-            continue;
-        }
+            let path = entry.path().to_path_buf();
+            // Adjust depth to be relative to the *start* directory
+            let depth = entry.depth().saturating_sub(1);
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.file_type().map_or(false, |ft| ft.is_dir());
 
-        let path = entry.path().to_path_buf();
-        // Adjust depth to be relative to the *start* directory
-        let depth = entry.depth().saturating_sub(1);
-        let name = entry.file_name().to_string_lossy().to_string();
-        let is_dir = entry.file_type().map_or(false, |ft| ft.is_dir());
+            let content = if is_dir {
+                None
+            } else {
+                match fs::read_to_string(&path) {
+                    Ok(content) => Some(content),
+                    Err(e) if e.kind() == io::ErrorKind::InvalidData && read_binary_lossy => {
+                        // -uuu: don't skip binary/non-UTF8 files, decode them lossily instead.
+                        fs::read(&path).ok().map(|bytes| {
+                            String::from_utf8_lossy(&bytes).into_owned()
+                        })
+                    }
+                    Err(e) => {
+                        if e.kind() != io::ErrorKind::InvalidData {
+                            eprintln!(
+                                "Warning: Could not read file '{}': {} (Skipping content)",
+                                path.display(),
+                                e
+                            );
+                        } else {
+                            eprintln!(
+                                "Info: Skipping binary or non-UTF8 file: '{}'",
+                                path.display()
+                            );
+                        }
+                        None
+                    }
+                }
+            };
+
+            // A send error means the receiver hung up, which can't happen
+            // before we've dropped our own `tx` below; ignore it either way.
+            let _ = tx.send(WalkItem {
+                path,
+                name,
+                is_dir,
+                depth,
+                content,
+            });
+
+            WalkState::Continue
+        })
+    });
+    drop(tx);
+
+    // Sorting by full path reproduces depth-first lexical order regardless
+    // of which worker thread visited which entry.
+    let mut items: Vec<WalkItem> = rx.into_iter().collect();
+    items.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut included_paths: HashSet<PathBuf> = HashSet::new();
+    for item in items {
+        included_paths.insert(item.path.clone());
 
         dir_entries.push(DirEntryInfo {
-            // path: path.clone(),
-            name,
-            is_dir,
-            depth,
+            name: item.name,
+            is_dir: item.is_dir,
+            depth: item.depth,
         });
 
-        if !is_dir {
-            match fs::read_to_string(&path) {
-                Ok(content) => {
-                    file_contents.push(FileInfo { path, content });
-                }
-                Err(e) => {
-                    if e.kind() != io::ErrorKind::InvalidData {
-                        writeln!(
-                            io::stderr(),
-                            "Warning: Could not read file '{}': {} (Skipping content)",
-                            path.display(),
-                            e
-                        )?;
-                    } else {
-                        writeln!(
-                            io::stderr(),
-                            "Info: Skipping binary or non-UTF8 file: '{}'",
-                            path.display()
-                        )?;
-                    }
-                }
-            }
+        if let Some(content) = item.content {
+            file_contents.push(FileInfo {
+                path: item.path,
+                content,
+            });
         }
     }
 
+    // --- --explain: figure out why every excluded path was left out ---
+    let ignored_entries = if args.explain {
+        find_ignored_entries(
+            &root_path,
+            &included_paths,
+            explain_types.as_ref().unwrap(),
+            &args.file_type,
+            explain_exclude_patterns.as_ref().unwrap(),
+            explain_gitignore.as_ref().unwrap(),
+        )
+    } else {
+        Vec::new()
+    };
+
     // --- Generate Output (Unchanged) ---
     match args.format {
         OutputFormat::Text => {
-            generate_text_output(&root_path, &dir_entries, &file_contents);
+            generate_text_output(&root_path, &dir_entries, &file_contents, &ignored_entries);
         }
         OutputFormat::Xml => {
-            generate_xml_output(&root_path, &dir_entries, &file_contents);
+            generate_xml_output(&root_path, &dir_entries, &file_contents, &ignored_entries);
         }
     }
 
@@ -213,6 +609,7 @@ fn generate_text_output(
     root_path: &Path,
     dir_entries: &[DirEntryInfo],
     file_contents: &[FileInfo],
+    ignored_entries: &[IgnoredEntry],
 ) {
     println!("--- Directory Tree ---");
     println!(
@@ -241,10 +638,28 @@ fn generate_text_output(
             println!();
         }
     }
+
+    if !ignored_entries.is_empty() {
+        println!("\n--- Ignored Entries ---");
+        for ignored in ignored_entries {
+            let display_path = ignored.path.strip_prefix(root_path).unwrap_or(&ignored.path);
+            println!(
+                "{}  (pattern: {}, source: {})",
+                display_path.display(),
+                ignored.pattern,
+                ignored.source
+            );
+        }
+    }
 }
 
 // --- XML Output Generation ---
-fn generate_xml_output(root_path: &Path, dir_entries: &[DirEntryInfo], file_contents: &[FileInfo]) {
+fn generate_xml_output(
+    root_path: &Path,
+    dir_entries: &[DirEntryInfo],
+    file_contents: &[FileInfo],
+    ignored_entries: &[IgnoredEntry],
+) {
     let mut xw = XmlWriter::new(Options::default());
     xw.start_element("projectContext");
     xw.write_attribute("rootPath", &root_path.to_string_lossy());
@@ -299,6 +714,20 @@ fn generate_xml_output(root_path: &Path, dir_entries: &[DirEntryInfo], file_cont
         }
     }
     xw.end_element(); // </fileContents>
+
+    if !ignored_entries.is_empty() {
+        xw.start_element("ignored");
+        for ignored in ignored_entries {
+            let display_path = ignored.path.strip_prefix(root_path).unwrap_or(&ignored.path);
+            xw.start_element("entry");
+            xw.write_attribute("path", &display_path.to_string_lossy());
+            xw.write_attribute("pattern", &ignored.pattern);
+            xw.write_attribute("source", &ignored.source);
+            xw.end_element(); // </entry>
+        }
+        xw.end_element(); // </ignored>
+    }
+
     xw.end_element(); // </projectContext>
     print!("{}", xw.end_document());
 }